@@ -0,0 +1,98 @@
+//! A high-level, reusable font-embedding builder.
+//!
+//! Owns the parsed ttf/OpenType face and the running subset state (which
+//! glyphs have been used, and which unicode text they represent), and
+//! exposes the handful of operations a caller needs to lay out text and
+//! then embed a correctly subsetted font: measuring strings, encoding them
+//! to glyph IDs while recording what's used, and producing the final
+//! `/ToUnicode` CMap. A caller can show several strings through the same
+//! `EmbeddedFont` and finalize one correctly-subsetted font at the end.
+//!
+//! Characters with no glyph fall back to GID 0 (`.notdef`) instead of
+//! panicking, and a glyph that several distinct codepoints map to (the
+//! font's cmap sends them to the same glyph) accumulates each of them once,
+//! so `to_unicode_cmap` can still
+//! map it back via `pair_with_multiple`.
+
+use std::collections::BTreeMap;
+
+use pdf_writer::types::UnicodeCmap;
+use ttf_parser::{Face, GlyphId};
+
+use crate::{CMAP_NAME, SYSTEM_INFO};
+
+pub struct EmbeddedFont<'a> {
+    face: Face<'a>,
+    /// GID -> the unicode text it represents, accumulated as strings are encoded.
+    glyph_set: BTreeMap<u16, String>,
+}
+
+impl<'a> EmbeddedFont<'a> {
+    pub fn new(face: Face<'a>) -> Self {
+        Self { face, glyph_set: BTreeMap::new() }
+    }
+
+    /// The parsed face, for metrics this type doesn't already surface.
+    pub fn face(&self) -> &Face<'a> {
+        &self.face
+    }
+
+    /// The glyphs used so far (by `encode`), each with the unicode text it
+    /// represents.
+    pub fn glyph_set(&self) -> &BTreeMap<u16, String> {
+        &self.glyph_set
+    }
+
+    /// Converts a value in font units (em-relative) to PDF's fixed 1000
+    /// units-per-em glyph space.
+    pub fn to_font_units(&self, v: f32) -> f32 {
+        (v / self.face.units_per_em() as f32) * 1000.0
+    }
+
+    fn glyph_for(&self, ch: char) -> GlyphId {
+        self.face.glyph_index(ch).unwrap_or(GlyphId(0))
+    }
+
+    /// The width of `text` set at `size` points, summing each character's
+    /// advance. Characters with no glyph contribute the `.notdef` advance
+    /// (usually 0) rather than panicking.
+    pub fn width_of_string(&self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .map(|ch| {
+                let advance = self.face.glyph_hor_advance(self.glyph_for(ch)).unwrap_or(0);
+                self.to_font_units(advance as f32) / 1000.0 * size
+            })
+            .sum()
+    }
+
+    /// Encodes `text` as a sequence of glyph IDs, recording each glyph as
+    /// used (and the text it represents, for `to_unicode_cmap`) as a side
+    /// effect. Characters with no glyph map to GID 0 (`.notdef`).
+    pub fn encode(&mut self, text: &str) -> Vec<u16> {
+        text.chars()
+            .map(|ch| {
+                let gid = self.glyph_for(ch).0;
+                let text = self.glyph_set.entry(gid).or_default();
+                if !text.contains(ch) {
+                    text.push(ch);
+                }
+                gid
+            })
+            .collect()
+    }
+
+    /// Builds the `/ToUnicode` CMap for every glyph encoded so far.
+    ///
+    /// GID 0 (`.notdef`) is skipped: it stands in for every character that
+    /// had no glyph, so mapping it back to their concatenated text would be
+    /// an incoherent reverse mapping rather than a useful one.
+    pub fn to_unicode_cmap(&self) -> UnicodeCmap {
+        let mut cmap = UnicodeCmap::new(CMAP_NAME, SYSTEM_INFO);
+        for (&gid, text) in &self.glyph_set {
+            if gid != 0 && !text.is_empty() {
+                cmap.pair_with_multiple(gid, text.chars());
+            }
+        }
+        cmap
+    }
+}
@@ -0,0 +1,59 @@
+//! Tracks the glyph ID renumbering that subsetting introduces.
+//!
+//! `subsetter::subset` drops unused glyphs and renumbers the ones it keeps,
+//! so the glyph IDs baked into the embedded font no longer line up with the
+//! original font's IDs — the ones used everywhere else (content stream
+//! encoding, `/W` widths, the `/ToUnicode` CMap). Rather than touch all of
+//! those, we keep CIDs equal to the *original* glyph IDs and add a
+//! `/CIDToGIDMap` stream that translates CID -> subsetted glyph ID, which is
+//! exactly what that PDF construct is for.
+
+use pdf_writer::{Finish, PdfWriter, Ref};
+use std::collections::BTreeMap;
+
+/// Maps original glyph IDs to the glyph IDs they were renumbered to by
+/// subsetting.
+pub struct GlyphRemap {
+    new_gid: BTreeMap<u16, u16>,
+}
+
+impl GlyphRemap {
+    /// Builds the remapping for a subsetter that keeps `.notdef` (glyph 0)
+    /// first and then assigns the remaining new glyph IDs in increasing
+    /// order of the original glyph IDs it was asked to keep. This matches
+    /// `subsetter::Profile::pdf`'s glyph ordering.
+    pub fn new(used_glyphs: impl IntoIterator<Item = u16>) -> Self {
+        let mut new_gid = BTreeMap::new();
+        new_gid.insert(0, 0);
+        let mut next = 1;
+        let mut used: Vec<u16> = used_glyphs.into_iter().collect();
+        used.sort_unstable();
+        for old_gid in used {
+            if old_gid != 0 {
+                new_gid.entry(old_gid).or_insert_with(|| {
+                    let gid = next;
+                    next += 1;
+                    gid
+                });
+            }
+        }
+        Self { new_gid }
+    }
+
+    /// The subsetted glyph ID that `old_gid` now lives at, or `0`
+    /// (`.notdef`) if `old_gid` wasn't kept.
+    pub fn new_gid(&self, old_gid: u16) -> u16 {
+        self.new_gid.get(&old_gid).copied().unwrap_or(0)
+    }
+
+    /// Writes a `/CIDToGIDMap` stream mapping every CID in `0..num_cids`
+    /// (CIDs here are the original glyph IDs) to its subsetted glyph ID, as
+    /// big-endian `u16` pairs.
+    pub fn write_cid_to_gid_map(&self, writer: &mut PdfWriter, map_ref: Ref, num_cids: u16) {
+        let mut data = Vec::with_capacity(num_cids as usize * 2);
+        for cid in 0..num_cids {
+            data.extend_from_slice(&self.new_gid(cid).to_be_bytes());
+        }
+        writer.stream(map_ref, &data).finish();
+    }
+}
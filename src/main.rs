@@ -1,5 +1,12 @@
 //! This example gives you a first introduction on how to use pdf-writer.
 
+mod embedded_font;
+mod glyph_remap;
+mod sfnt;
+mod standard_fonts;
+mod type1;
+mod widths;
+
 use std::collections::BTreeMap;
 use std::fs;
 use std::hash::{Hash};
@@ -9,12 +16,19 @@ use pdf_writer::{Content, Filter, Finish, Name, PdfWriter, Rect, Ref, Str, TextS
 use siphasher::sip128::{Hasher128, SipHasher13};
 use ttf_parser::GlyphId;
 
-const SYSTEM_INFO: SystemInfo = SystemInfo {
+use embedded_font::EmbeddedFont;
+use glyph_remap::GlyphRemap;
+use sfnt::OutlineFormat;
+use standard_fonts::StandardFont;
+use type1::Type1Program;
+use widths::WidthEncoder;
+
+pub(crate) const SYSTEM_INFO: SystemInfo = SystemInfo {
     registry: Str(b"Adobe"),
     ordering: Str(b"Identity"),
     supplement: 0,
 };
-const CMAP_NAME: Name = Name(b"Custom");
+pub(crate) const CMAP_NAME: Name = Name(b"Custom");
 
 
 fn main() -> std::io::Result<()> {
@@ -22,9 +36,14 @@ fn main() -> std::io::Result<()> {
     let mut font_db = fontdb::Database::new();
     font_db.load_system_fonts();
 
-    // Query font database for a particular font
+    // Query font database for a particular font. "DejaVu Sans" is chosen
+    // deliberately over something like "Helvetica": it's a common system
+    // font but, unlike Helvetica and its usual substitutes (Arial, Nimbus
+    // Sans, Liberation Sans, ...), it isn't one of the 14 standard PDF
+    // fonts, so this demo actually exercises the subsetting/embedding path
+    // below instead of always taking the early standard-font return.
     let font_id = font_db.query(&fontdb::Query {
-        families: &[fontdb::Family::Name("Helvetica")],
+        families: &[fontdb::Family::Name("DejaVu Sans")],
         weight: Default::default(),
         stretch: Default::default(),
         style: Default::default(),
@@ -32,6 +51,21 @@ fn main() -> std::io::Result<()> {
 
     let face = font_db.face(font_id).unwrap();
 
+    // Specify the string we want to display
+    let message = "Hello World from Rust!";
+
+    // If the resolved font is one of the 14 standard PDF fonts, skip
+    // subsetting and embedding entirely: every PDF reader already has them,
+    // so a bare `/BaseFont` name is all that's needed.
+    let standard_font = standard_fonts::detect(
+        &face.post_script_name,
+        face.weight.0 >= fontdb::Weight::BOLD.0,
+        face.style != fontdb::Style::Normal,
+    );
+    if let Some(standard_font) = standard_font {
+        return write_standard_font_pdf(standard_font, message);
+    }
+
     // Read source data
     let font_data = match &face.source {
         Source::Binary(b) => { Vec::from(b.as_ref().as_ref())}
@@ -39,26 +73,46 @@ fn main() -> std::io::Result<()> {
         Source::SharedFile(f, _) => { fs::read(f).unwrap() }
     };
 
+    // Some installed "fonts" are classic Type1 programs (PFB/PFA) rather
+    // than sfnt files, which `ttf_parser::Face::parse` can't read. Detect
+    // and embed those through the simple, non-CID Type1 path instead.
+    if type1::looks_like_type1(&font_data) {
+        let program = type1::parse_type1_program(&font_data)
+            .expect("Failed to parse Type1 font program");
+        return write_type1_font_pdf(&face.post_script_name, &program, message);
+    }
+
     // Parse as ttf font
     let ttf = ttf_parser::Face::parse(
         font_data.as_slice(), face.index
     ).expect("Failed to parse font data as ttf");
 
-    // Conversion function from ttf values in em to PDFs font units
-    let to_font_units = |v: f32| (v / ttf.units_per_em() as f32) * 1000.0;
+    // Determine which outline flavor the font uses so we can pick the right
+    // descendant font subtype and FontFile* embedding below. `CFF2` fonts
+    // (used for variable fonts) aren't understood by Acrobat when dropped
+    // straight into a FontFile3 stream, so we bail out with a clear error
+    // instead of writing a PDF that can't be opened.
+    let outline_format = sfnt::detect_outline_format(&font_data);
+    if outline_format == OutlineFormat::Cff2 {
+        return Err(std::io::Error::other(
+            "fonts with CFF2 outlines are not supported; down-convert to CFF or TrueType first",
+        ));
+    }
 
-    // Specify the string we want to display
-    let message = "Hello World from Rust!";
+    // Owns the face plus the running subset state: which glyphs `encode`
+    // has used so far and the unicode text they represent.
+    let mut font = EmbeddedFont::new(ttf);
 
-    // Get Vec of the 16-bit glyph number for each unicode character
-    let message_glyphs: Vec<_> = message.chars().map(|ch| ttf.glyph_index(ch).unwrap().0 ).collect();
+    // Get the 16-bit glyph number for each character, recording each glyph
+    // as used as a side effect.
+    let message_glyphs = font.encode(message);
 
-    // Build mapping from glyph to unicode character string
-    let mut glyph_set: BTreeMap<u16, String> = BTreeMap::new();
-    for ch in message.chars() {
-        let Some(glyph) = ttf.glyph_index(ch) else { continue };
-        glyph_set.entry(glyph.0).or_insert_with(|| ch.to_string());
-    }
+    const FONT_SIZE: f32 = 14.0;
+    const PAGE_WIDTH: f32 = 595.0;
+    // Measure the message so it can be centered on the page instead of
+    // starting at a fixed x-coordinate.
+    let message_width = font.width_of_string(message, FONT_SIZE);
+    let start_x = (PAGE_WIDTH - message_width) / 2.0;
 
     // Start writing PDF
     let mut writer = PdfWriter::new();
@@ -73,6 +127,7 @@ fn main() -> std::io::Result<()> {
     let cmap_ref = Ref::new(7);
     let data_ref = Ref::new(8);
     let content_ref = Ref::new(9);
+    let cid_to_gid_ref = Ref::new(10);
 
     let font_name = Name(b"F1");
 
@@ -100,7 +155,7 @@ fn main() -> std::io::Result<()> {
     // fonts shipped with every PDF reader, we don't have to embed any font
     // data.
     let postscript_name = face.post_script_name.clone();
-    let subset_tag = subset_tag(&glyph_set);
+    let subset_tag = subset_tag(font.glyph_set());
     let base_font = format!("{subset_tag}+{postscript_name}");
     writer
         .type0_font(type0_ref)
@@ -109,62 +164,99 @@ fn main() -> std::io::Result<()> {
         .descendant_font(cid_ref)
         .to_unicode(cmap_ref);
 
-    // Write the CID font referencing the font descriptor.
+    // Write the CID font referencing the font descriptor. TrueType outlines
+    // use CIDFontType2 (glyf-keyed); CFF outlines use CIDFontType0 (charstring-keyed).
+    let cid_font_type = match outline_format {
+        OutlineFormat::TrueType => CidFontType::Type2,
+        OutlineFormat::Cff => CidFontType::Type0,
+        OutlineFormat::Cff2 => unreachable!("rejected above"),
+    };
+    // Subset now (rather than where the font program bytes get written
+    // below) because whether it succeeded decides how CIDs are numbered:
+    // `subsetter::subset` renumbers glyph IDs starting at 1 in ascending
+    // original-GID order, so the subsetted font's GIDs no longer match the
+    // original GIDs used elsewhere (content stream, `/W`, `/ToUnicode`). But
+    // subsetting can fail, in which case we fall back to embedding the
+    // *original, unrenumbered* font data further down — and CIDs must then
+    // stay identity GIDs to match what's actually embedded.
+    let glyphs: Vec<_> = font.glyph_set().keys().copied().collect();
+    let profile = subsetter::Profile::pdf(&glyphs);
+    let subsetted_result = subsetter::subset(&font_data, face.index, profile);
+    let subsetting_succeeded = subsetted_result.is_ok();
+    let subsetted: &[u8] = subsetted_result.as_deref().unwrap_or(&font_data);
+
+    // `/CIDToGIDMap` is how CIDFontType2 reconciles renumbered GIDs, but
+    // it's only meaningful there — a CIDFontType0 (CFF) dict has no such
+    // key, so for CFF the CIDs themselves must already be the subsetter's
+    // GIDs. Either way, only build the remap when subsetting actually
+    // renumbered anything; otherwise identity GIDs are the correct CIDs.
+    let glyph_remap =
+        subsetting_succeeded.then(|| GlyphRemap::new(font.glyph_set().keys().copied()));
+    let cid_for_gid = |gid: u16| match outline_format {
+        OutlineFormat::TrueType => gid,
+        OutlineFormat::Cff => match &glyph_remap {
+            Some(remap) => remap.new_gid(gid),
+            None => gid,
+        },
+        OutlineFormat::Cff2 => unreachable!("rejected above"),
+    };
+
+    // Compute widths for the CIDs actually used.
+    let num_glyphs = font.face().number_of_glyphs();
+    let mut width_encoder = WidthEncoder::new();
+    for g in font.glyph_set().keys().copied() {
+        let advance = font.face().glyph_hor_advance(GlyphId(g)).unwrap_or(0);
+        width_encoder.insert(cid_for_gid(g), font.to_font_units(advance as f32));
+    }
+    let default_width = width_encoder.most_common_width().unwrap_or(0.0);
+
     let mut cid = writer.cid_font(cid_ref);
-    cid.subtype( CidFontType::Type2);
+    cid.subtype(cid_font_type);
     cid.base_font(Name(base_font.as_bytes()));
     cid.system_info(SYSTEM_INFO);
     cid.font_descriptor(descriptor_ref);
-    cid.default_width(0.0);
-    cid.cid_to_gid_map_predefined(Name(b"Identity"));
-
-    // Compute widths
-    let num_glyphs = ttf.number_of_glyphs();
-    let mut widths = vec![0.0; num_glyphs as usize];
-    for g in glyph_set.keys().copied() {
-        let x= ttf.glyph_hor_advance(GlyphId(g)).unwrap_or(0);
-        widths[g as usize] = to_font_units(x as f32);
+    cid.default_width(default_width);
+    if outline_format == OutlineFormat::TrueType && glyph_remap.is_some() {
+        // CIDs == original GIDs here, so translate to the subsetted font's
+        // GIDs via `/CIDToGIDMap`. Left as the spec's default Identity
+        // mapping when subsetting failed, since the embedded font below is
+        // then the original, unrenumbered one.
+        cid.cid_to_gid_map(cid_to_gid_ref);
     }
 
-    // Write all non-zero glyph widths.
-    let mut start = 0;
-    let mut start_width = widths[0];
     let mut width_writer = cid.widths();
-    for (i, w) in widths.iter().enumerate().skip(1) {
-        if *w != start_width || i == widths.len() - 1 {
-            if start_width != 0.0 {
-                width_writer.same(start as u16, i as u16, start_width);
-            }
-            start = i as i32;
-            start_width = *w;
-        }
-    }
-
+    width_encoder.write(&mut width_writer);
     width_writer.finish();
     cid.finish();
 
     // Flags
     let mut flags = FontFlags::empty();
     flags.set(FontFlags::SERIF, postscript_name.contains("Serif"));
-    flags.set(FontFlags::FIXED_PITCH, ttf.is_monospaced());
-    flags.set(FontFlags::ITALIC, ttf.is_italic());
+    flags.set(FontFlags::FIXED_PITCH, font.face().is_monospaced());
+    flags.set(FontFlags::ITALIC, font.face().is_italic());
     flags.insert(FontFlags::SYMBOLIC);
     flags.insert(FontFlags::SMALL_CAP);
 
     // bounding box
-    let global_bbox = ttf.global_bounding_box();
+    let global_bbox = font.face().global_bounding_box();
     let bbox = Rect::new(
-        to_font_units(global_bbox.x_min.into()),
-        to_font_units(global_bbox.y_min.into()),
-        to_font_units(global_bbox.x_max.into()),
-        to_font_units(global_bbox.y_max.into()),
+        font.to_font_units(global_bbox.x_min.into()),
+        font.to_font_units(global_bbox.y_min.into()),
+        font.to_font_units(global_bbox.x_max.into()),
+        font.to_font_units(global_bbox.y_max.into()),
     );
 
-    let italic_angle = ttf.italic_angle().unwrap_or(0.0);
-    let ascender = to_font_units(ttf.typographic_ascender().unwrap_or(ttf.ascender()).into());
-    let descender = to_font_units(ttf.typographic_descender().unwrap_or(ttf.descender()).into());
-    let cap_height = to_font_units(ttf.capital_height().unwrap_or(ttf.ascender()).into());
-    let stem_v = 10.0 + 0.244 * (f32::from(ttf.weight().to_number()) - 50.0);
+    let italic_angle = font.face().italic_angle().unwrap_or(0.0);
+    let ascender = font.to_font_units(
+        font.face().typographic_ascender().unwrap_or(font.face().ascender()).into(),
+    );
+    let descender = font.to_font_units(
+        font.face().typographic_descender().unwrap_or(font.face().descender()).into(),
+    );
+    let cap_height = font.to_font_units(
+        font.face().capital_height().unwrap_or(font.face().ascender()).into(),
+    );
+    let stem_v = 10.0 + 0.244 * (f32::from(font.face().weight().to_number()) - 50.0);
 
     // Write the font descriptor (contains metrics about the font).
     let mut font_descriptor = writer.font_descriptor(descriptor_ref);
@@ -178,35 +270,76 @@ fn main() -> std::io::Result<()> {
         .cap_height(cap_height)
         .stem_v(stem_v);
 
-    font_descriptor.font_file2(data_ref);
+    match outline_format {
+        OutlineFormat::TrueType => {
+            font_descriptor.font_file2(data_ref);
+        }
+        OutlineFormat::Cff => {
+            font_descriptor.font_file3(data_ref);
+        }
+        OutlineFormat::Cff2 => unreachable!("rejected above"),
+    }
     font_descriptor.finish();
 
     // Write the /ToUnicode character map, which maps glyph ids back to
     // unicode codepoints to enable copying out of the PDF.
-    let cmap = create_cmap(&glyph_set);
+    // Keyed by CID through `cid_for_gid`, like the content stream, so CFF
+    // output maps the subsetter's GIDs; `.notdef` is skipped as in
+    // `EmbeddedFont::to_unicode_cmap`.
+    let mut cmap = UnicodeCmap::new(CMAP_NAME, SYSTEM_INFO);
+    for (&gid, text) in font.glyph_set() {
+        if gid != 0 && !text.is_empty() {
+            cmap.pair_with_multiple(cid_for_gid(gid), text.chars());
+        }
+    }
     writer.cmap(cmap_ref, &cmap.finish());
 
-    let glyphs: Vec<_> = glyph_set.keys().copied().collect();
-    let profile = subsetter::Profile::pdf(&glyphs);
-    let subsetted = subsetter::subset(&font_data, face.index, profile);
-    let mut subset_font_data = deflate(subsetted.as_deref().unwrap_or(&font_data));
+    // Write the /CIDToGIDMap translating CIDs (== original GIDs) to the
+    // glyph IDs they were renumbered to inside the subsetted font. Only
+    // CIDFontType2 (TrueType) references this (and only when subsetting
+    // actually renumbered anything); CIDFontType0 (CFF) CIDs are already
+    // the subsetted GIDs via `cid_for_gid` above.
+    if outline_format == OutlineFormat::TrueType {
+        if let Some(remap) = &glyph_remap {
+            remap.write_cid_to_gid_map(&mut writer, cid_to_gid_ref, num_glyphs);
+        }
+    }
+
+    // For CFF outlines, prefer embedding the bare `CFF ` table rather than
+    // the whole sfnt wrapper: it's what the FontFile3 `CIDFontType0C`
+    // subtype is meant for, and it's smaller. Fall back to embedding the
+    // whole (subsetted) OpenType file if the table can't be pulled out on
+    // its own.
+    let (font_program, opentype_subtype) = match outline_format {
+        OutlineFormat::TrueType => (subsetted, None),
+        OutlineFormat::Cff => match sfnt::find_table(subsetted, b"CFF ") {
+            Some(cff) => (cff, Some(Name(b"CIDFontType0C"))),
+            None => (subsetted, Some(Name(b"OpenType"))),
+        },
+        OutlineFormat::Cff2 => unreachable!("rejected above"),
+    };
+    let subset_font_data = deflate(font_program);
 
-    // println!("subset_font_data: {:?}", &subset_font_data[..20]);
     let mut stream = writer.stream(data_ref, &subset_font_data);
     stream.filter(Filter::FlateDecode);
+    if let Some(subtype) = opentype_subtype {
+        stream.pair(Name(b"Subtype"), subtype);
+    }
     stream.finish();
 
-    // Encode u16 glyphs as pairs of u8 bytes
+    // Encode u16 CIDs as pairs of u8 bytes, translating through
+    // `cid_for_gid` so CFF output addresses the subsetter's own GIDs.
     let mut encoded = vec![];
     for g in message_glyphs {
-        encoded.push((g >> 8) as u8);
-        encoded.push((g & 0xff) as u8);
+        let cid = cid_for_gid(g);
+        encoded.push((cid >> 8) as u8);
+        encoded.push((cid & 0xff) as u8);
     }
 
     let mut content = Content::new();
     content.begin_text();
-    content.set_font(font_name, 14.0);
-    content.next_line(108.0, 734.0);
+    content.set_font(font_name, FONT_SIZE);
+    content.next_line(start_x, 734.0);
     content.show(Str(encoded.as_slice()));
     content.end_text();
     writer.stream(content_ref, &content.finish());
@@ -219,25 +352,117 @@ fn main() -> std::io::Result<()> {
     fs::write("target/hello_embed.pdf", buf)
 }
 
-fn deflate(data: &[u8]) -> Vec<u8> {
-    const COMPRESSION_LEVEL: u8 = 6;
-    miniz_oxide::deflate::compress_to_vec_zlib(data, COMPRESSION_LEVEL)
+/// Writes a PDF that shows `message` in one of the 14 standard fonts,
+/// referenced by name only — no font file is embedded.
+fn write_standard_font_pdf(font: StandardFont, message: &str) -> std::io::Result<()> {
+    let mut writer = PdfWriter::new();
+
+    let catalog_ref = Ref::new(1);
+    let page_tree_ref = Ref::new(2);
+    let page_ref = Ref::new(3);
+    let font_ref = Ref::new(4);
+    let content_ref = Ref::new(5);
+
+    let font_name = Name(b"F1");
+
+    writer.catalog(catalog_ref).pages(page_tree_ref);
+    writer.pages(page_tree_ref).kids([page_ref]).count(1);
+
+    let mut page = writer.page(page_ref);
+    page.media_box(Rect::new(0.0, 0.0, 595.0, 842.0));
+    page.parent(page_tree_ref);
+    page.contents(content_ref);
+    page.resources().fonts().pair(font_name, font_ref);
+    page.finish();
+
+    writer
+        .type1_font(font_ref)
+        .base_font(Name(font.base_font_name().as_bytes()));
+
+    let mut content = Content::new();
+    content.begin_text();
+    content.set_font(font_name, 14.0);
+    content.next_line(108.0, 734.0);
+    content.show(Str(message.as_bytes()));
+    content.end_text();
+    writer.stream(content_ref, &content.finish());
+
+    let buf: Vec<u8> = writer.finish();
+    fs::write("target/hello_embed.pdf", buf)
 }
 
-/// Create a /ToUnicode CMap.
-fn create_cmap(
-    glyph_set: &BTreeMap<u16, String>,
-) -> UnicodeCmap {
+/// Writes a PDF that shows `message` in an embedded Type1 (PFB/PFA) font,
+/// via a simple (non-CID) font referencing a `FontFile` with
+/// `/Length1`/`/Length2`/`/Length3`.
+///
+/// `message` is shown as raw bytes against the font's built-in encoding, so
+/// this only works for ASCII text (no CID/GID lookup is available for a
+/// Type1 charstring program without a full PostScript interpreter).
+fn write_type1_font_pdf(
+    postscript_name: &str,
+    program: &Type1Program,
+    message: &str,
+) -> std::io::Result<()> {
+    let mut writer = PdfWriter::new();
 
-    // Produce a reverse mapping from glyphs to unicode strings.
-    let mut cmap = UnicodeCmap::new(CMAP_NAME, SYSTEM_INFO);
-    for (&g, text) in glyph_set.iter() {
-        if !text.is_empty() {
-            cmap.pair_with_multiple(g, text.chars());
-        }
-    }
+    let catalog_ref = Ref::new(1);
+    let page_tree_ref = Ref::new(2);
+    let page_ref = Ref::new(3);
+    let font_ref = Ref::new(4);
+    let descriptor_ref = Ref::new(5);
+    let file_ref = Ref::new(6);
+    let content_ref = Ref::new(7);
+
+    let font_name = Name(b"F1");
+
+    writer.catalog(catalog_ref).pages(page_tree_ref);
+    writer.pages(page_tree_ref).kids([page_ref]).count(1);
 
-    cmap
+    let mut page = writer.page(page_ref);
+    page.media_box(Rect::new(0.0, 0.0, 595.0, 842.0));
+    page.parent(page_tree_ref);
+    page.contents(content_ref);
+    page.resources().fonts().pair(font_name, font_ref);
+    page.finish();
+
+    writer
+        .type1_font(font_ref)
+        .base_font(Name(postscript_name.as_bytes()))
+        .font_descriptor(descriptor_ref);
+
+    // We don't parse the Type1 charstrings, so we don't have real metrics;
+    // report a symbolic font with an empty bounding box rather than
+    // fabricating numbers.
+    let mut font_descriptor = writer.font_descriptor(descriptor_ref);
+    font_descriptor
+        .name(Name(postscript_name.as_bytes()))
+        .flags(FontFlags::SYMBOLIC)
+        .bbox(Rect::new(0.0, 0.0, 0.0, 0.0))
+        .italic_angle(0.0)
+        .ascent(0.0)
+        .descent(0.0)
+        .cap_height(0.0)
+        .stem_v(0.0);
+    font_descriptor.font_file(file_ref);
+    font_descriptor.finish();
+
+    type1::embed_type1(&mut writer, file_ref, program);
+
+    let mut content = Content::new();
+    content.begin_text();
+    content.set_font(font_name, 14.0);
+    content.next_line(108.0, 734.0);
+    content.show(Str(message.as_bytes()));
+    content.end_text();
+    writer.stream(content_ref, &content.finish());
+
+    let buf: Vec<u8> = writer.finish();
+    fs::write("target/hello_embed.pdf", buf)
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    const COMPRESSION_LEVEL: u8 = 6;
+    miniz_oxide::deflate::compress_to_vec_zlib(data, COMPRESSION_LEVEL)
 }
 
 /// Produce a unique 6 letter tag for a glyph set.
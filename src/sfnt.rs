@@ -0,0 +1,45 @@
+//! Minimal sfnt (OpenType/TrueType) table directory parsing.
+//!
+//! `ttf_parser` already exposes the tables it understands, but it won't hand
+//! back raw, unparsed tables like `CFF `/`CFF2`. We only need to answer one
+//! question — does this font carry PostScript outlines or TrueType `glyf`
+//! outlines — so a tiny manual walk of the table directory is enough.
+
+/// Which outline format a parsed sfnt font carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineFormat {
+    /// `glyf`/`loca` TrueType outlines.
+    TrueType,
+    /// PostScript outlines in a `CFF ` table.
+    Cff,
+    /// PostScript outlines in a `CFF2` table (used by variable fonts).
+    Cff2,
+}
+
+/// Looks up a table by tag in the sfnt directory of `data` and returns its
+/// raw bytes, or `None` if `data` isn't a well-formed sfnt file or doesn't
+/// contain the table.
+pub fn find_table<'a>(data: &'a [u8], tag: &[u8; 4]) -> Option<&'a [u8]> {
+    let num_tables = u16::from_be_bytes(data.get(4..6)?.try_into().ok()?);
+    for i in 0..num_tables {
+        let record = data.get(12 + i as usize * 16..12 + i as usize * 16 + 16)?;
+        if &record[0..4] == tag {
+            let offset = u32::from_be_bytes(record[8..12].try_into().ok()?) as usize;
+            let len = u32::from_be_bytes(record[12..16].try_into().ok()?) as usize;
+            return data.get(offset..offset.checked_add(len)?);
+        }
+    }
+    None
+}
+
+/// Detects which outline format `data` (a full sfnt file) uses by checking
+/// for the presence of a `CFF `/`CFF2` table.
+pub fn detect_outline_format(data: &[u8]) -> OutlineFormat {
+    if find_table(data, b"CFF2").is_some() {
+        OutlineFormat::Cff2
+    } else if find_table(data, b"CFF ").is_some() {
+        OutlineFormat::Cff
+    } else {
+        OutlineFormat::TrueType
+    }
+}
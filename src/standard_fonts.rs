@@ -0,0 +1,117 @@
+//! Detection of the 14 standard PDF fonts.
+//!
+//! Every PDF reader ships Helvetica, Courier, Times (each with their
+//! bold/italic/bold-italic variants), Symbol and ZapfDingbats, so fonts that
+//! resolve to one of them don't need subsetting or embedding at all — a
+//! bare `/BaseFont` name is enough. Font databases hand back whatever the
+//! installed substitute is actually called (`ArialMT`, `NimbusSans-Regular`,
+//! ...), so this matches on a table of common aliases rather than requiring
+//! an exact PostScript name.
+
+/// One of the 14 standard PDF fonts, named as PDF readers expect them in
+/// `/BaseFont`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardFont {
+    Helvetica,
+    HelveticaBold,
+    HelveticaOblique,
+    HelveticaBoldOblique,
+    Courier,
+    CourierBold,
+    CourierOblique,
+    CourierBoldOblique,
+    TimesRoman,
+    TimesBold,
+    TimesItalic,
+    TimesBoldItalic,
+    Symbol,
+    ZapfDingbats,
+}
+
+impl StandardFont {
+    /// The canonical `/BaseFont` name a PDF reader recognizes.
+    pub fn base_font_name(self) -> &'static str {
+        match self {
+            StandardFont::Helvetica => "Helvetica",
+            StandardFont::HelveticaBold => "Helvetica-Bold",
+            StandardFont::HelveticaOblique => "Helvetica-Oblique",
+            StandardFont::HelveticaBoldOblique => "Helvetica-BoldOblique",
+            StandardFont::Courier => "Courier",
+            StandardFont::CourierBold => "Courier-Bold",
+            StandardFont::CourierOblique => "Courier-Oblique",
+            StandardFont::CourierBoldOblique => "Courier-BoldOblique",
+            StandardFont::TimesRoman => "Times-Roman",
+            StandardFont::TimesBold => "Times-Bold",
+            StandardFont::TimesItalic => "Times-Italic",
+            StandardFont::TimesBoldItalic => "Times-BoldItalic",
+            StandardFont::Symbol => "Symbol",
+            StandardFont::ZapfDingbats => "ZapfDingbats",
+        }
+    }
+}
+
+/// Recognizes `name` (a PostScript or family name, as reported by a font
+/// database) as one of the 14 standard PDF fonts, resolving common aliases
+/// for the base family and folding in `bold`/`italic` to pick the variant.
+///
+/// Mirrors the many-to-one base-font name tables PDF readers themselves use
+/// to substitute missing fonts.
+pub fn detect(name: &str, bold: bool, italic: bool) -> Option<StandardFont> {
+    // Drop a PostScript style suffix (`NimbusSans-Regular`, `Arial-BoldMT`);
+    // the variant comes from `bold`/`italic` instead.
+    let family = name.split('-').next().unwrap_or(name);
+    let normalized = family.to_ascii_lowercase().replace([' ', '_'], "");
+
+    const HELVETICA_ALIASES: &[&str] =
+        &["helvetica", "arial", "arialmt", "nimbussans", "nimbussansl", "liberationsans"];
+    const COURIER_ALIASES: &[&str] = &[
+        "courier",
+        "couriernew",
+        "couriernewpsmt",
+        "nimbusmono",
+        "nimbusmonol",
+        "liberationmono",
+    ];
+    const TIMES_ALIASES: &[&str] = &[
+        "times",
+        "timesroman",
+        "timesnewroman",
+        "timesnewromanpsmt",
+        "nimbusroman",
+        "nimbusromanno9l",
+        "liberationserif",
+    ];
+
+    if HELVETICA_ALIASES.contains(&normalized.as_str()) {
+        return Some(match (bold, italic) {
+            (false, false) => StandardFont::Helvetica,
+            (true, false) => StandardFont::HelveticaBold,
+            (false, true) => StandardFont::HelveticaOblique,
+            (true, true) => StandardFont::HelveticaBoldOblique,
+        });
+    }
+    if COURIER_ALIASES.contains(&normalized.as_str()) {
+        return Some(match (bold, italic) {
+            (false, false) => StandardFont::Courier,
+            (true, false) => StandardFont::CourierBold,
+            (false, true) => StandardFont::CourierOblique,
+            (true, true) => StandardFont::CourierBoldOblique,
+        });
+    }
+    if TIMES_ALIASES.contains(&normalized.as_str()) {
+        return Some(match (bold, italic) {
+            (false, false) => StandardFont::TimesRoman,
+            (true, false) => StandardFont::TimesBold,
+            (false, true) => StandardFont::TimesItalic,
+            (true, true) => StandardFont::TimesBoldItalic,
+        });
+    }
+    if normalized == "symbol" {
+        return Some(StandardFont::Symbol);
+    }
+    if normalized == "zapfdingbats" || normalized == "dingbats" {
+        return Some(StandardFont::ZapfDingbats);
+    }
+
+    None
+}
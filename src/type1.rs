@@ -0,0 +1,156 @@
+//! Type1 / PFB font embedding.
+//!
+//! Type1 fonts don't go through the CID/Type0 flow the rest of this example
+//! uses for TrueType/CFF outlines — they're embedded as a simple,
+//! non-CID font instead. That needs its own small pipeline: reassemble the
+//! raw PostScript program (from a PFB's segments, or a PFA's hex-encoded
+//! binary region), split it into its three regions, and report their
+//! lengths for the `FontFile` stream's `/Length1`, `/Length2` and `/Length3`
+//! keys.
+
+use pdf_writer::{Finish, Name, PdfWriter, Ref};
+
+/// The three regions of a Type1 font program, as required by the PDF
+/// `FontFile` stream's `/Length1`, `/Length2` and `/Length3` keys.
+pub struct Type1Program {
+    /// Clear-text header, binary eexec region and fixed trailer, concatenated.
+    pub data: Vec<u8>,
+    /// Byte length of the ASCII clear-text header (including `eexec`).
+    pub length1: usize,
+    /// Byte length of the binary eexec-encrypted region.
+    pub length2: usize,
+    /// Byte length of the fixed 512-zero/`cleartomark` trailer.
+    pub length3: usize,
+}
+
+const PFB_MARKER: u8 = 0x80;
+const PFB_ASCII: u8 = 0x01;
+const PFB_BINARY: u8 = 0x02;
+const PFB_EOF: u8 = 0x03;
+
+/// Whether `data` looks like a Type1 font program: a PFB file starts with
+/// the `0x80` segment marker, and a PFA is plain ASCII PostScript starting
+/// with the `%!` header every Type1 program uses.
+pub fn looks_like_type1(data: &[u8]) -> bool {
+    data.first() == Some(&PFB_MARKER) || data.starts_with(b"%!")
+}
+
+/// Parses a Type1 font program from either PFB (segmented) or PFA
+/// (all-ASCII, with a hex-encoded binary region) bytes.
+pub fn parse_type1_program(data: &[u8]) -> Option<Type1Program> {
+    if data.first() == Some(&PFB_MARKER) {
+        parse_pfb(data)
+    } else {
+        parse_pfa(data)
+    }
+}
+
+/// Walks PFB segment markers (`0x80` + type byte + little-endian u32 length)
+/// to reassemble the ASCII and binary regions.
+fn parse_pfb(data: &[u8]) -> Option<Type1Program> {
+    let mut ascii = Vec::new();
+    let mut binary = Vec::new();
+    let mut i = 0;
+    loop {
+        if data.get(i).copied() != Some(PFB_MARKER) {
+            return None;
+        }
+        let kind = *data.get(i + 1)?;
+        if kind == PFB_EOF {
+            break;
+        }
+        let len = u32::from_le_bytes(data.get(i + 2..i + 6)?.try_into().ok()?) as usize;
+        let segment = data.get(i + 6..i + 6 + len)?;
+        match kind {
+            PFB_ASCII if binary.is_empty() => ascii.extend_from_slice(segment),
+            // An ASCII segment after the binary one is the trailer, which
+            // `assemble` appends itself.
+            PFB_ASCII => {}
+            PFB_BINARY => binary.extend_from_slice(segment),
+            _ => return None,
+        }
+        i += 6 + len;
+    }
+    Some(assemble(ascii, binary))
+}
+
+/// Splits a PFA at the `eexec` keyword and hex-decodes the binary region
+/// that follows it, stopping before the fixed 512-zero/`cleartomark`
+/// trailer rather than folding it into the decoded data.
+fn parse_pfa(data: &[u8]) -> Option<Type1Program> {
+    let text = std::str::from_utf8(data).ok()?;
+    let eexec_at = text.find("eexec")?;
+    let mut ascii_end = eexec_at + "eexec".len();
+    if data.get(ascii_end) == Some(&b'\r') {
+        ascii_end += 1;
+    }
+    if data.get(ascii_end) == Some(&b'\n') {
+        ascii_end += 1;
+    }
+
+    let ascii = data[..ascii_end].to_vec();
+    let rest = &text[ascii_end..];
+    let cleartomark_at = rest.find("cleartomark")?;
+    let before_trailer = &rest[..cleartomark_at];
+
+    // The trailer is exactly 512 literal ASCII '0' characters (as 8 lines of
+    // 64, each followed by a newline) ahead of `cleartomark` — structural
+    // padding, not hex-encoded data. Trim precisely those 512 characters
+    // rather than every trailing zero/whitespace character, so a `0x00`
+    // byte at the end of the (effectively random) encrypted data isn't
+    // mistaken for trailer padding and stripped along with it.
+    const TRAILER_ZEROS: usize = 512;
+    let mut hex: String =
+        before_trailer.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    if hex.len() < TRAILER_ZEROS || !hex[hex.len() - TRAILER_ZEROS..].bytes().all(|b| b == b'0') {
+        return None;
+    }
+    hex.truncate(hex.len() - TRAILER_ZEROS);
+    let binary = hex_decode(&hex)?;
+    Some(assemble(ascii, binary))
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+/// The fixed Type1 trailer: 512 ASCII `0` characters, as 8 lines of 64, followed by `cleartomark`.
+fn trailer() -> Vec<u8> {
+    let mut t = Vec::new();
+    for _ in 0..8 {
+        t.extend_from_slice(&[b'0'; 64]);
+        t.push(b'\n');
+    }
+    t.extend_from_slice(b"cleartomark\n");
+    t
+}
+
+fn assemble(ascii: Vec<u8>, binary: Vec<u8>) -> Type1Program {
+    let length1 = ascii.len();
+    let length2 = binary.len();
+    let trailer = trailer();
+    let length3 = trailer.len();
+
+    let mut data = ascii;
+    data.extend_from_slice(&binary);
+    data.extend_from_slice(&trailer);
+
+    Type1Program { data, length1, length2, length3 }
+}
+
+/// Embeds a Type1 font program as a `FontFile` stream (with the required
+/// `/Length1`/`/Length2`/`/Length3` keys) at `file_ref`, for a caller to
+/// reference from a simple font's descriptor.
+pub fn embed_type1(writer: &mut PdfWriter, file_ref: Ref, program: &Type1Program) {
+    let mut stream = writer.stream(file_ref, &program.data);
+    stream.pair(Name(b"Length1"), program.length1 as i32);
+    stream.pair(Name(b"Length2"), program.length2 as i32);
+    stream.pair(Name(b"Length3"), program.length3 as i32);
+    stream.finish();
+}
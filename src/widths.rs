@@ -0,0 +1,79 @@
+//! Encodes CID advance widths into the PDF `/W` array's two forms.
+//!
+//! A naive writer emits one `c_first c_last w` range per glyph, which wastes
+//! space once you have more than a handful of glyphs. This groups
+//! contiguous, equal-width runs of three or more CIDs into the range form
+//! (`c_first c_last w`) and leaves shorter or varying runs as the
+//! consecutive-list form (`c [w_a w_b w_c ...]`), and only ever emits
+//! entries for CIDs that are actually used.
+
+use pdf_writer::writers::Widths;
+use std::collections::BTreeMap;
+
+/// Builds a CID font's `/W` array from the widths of the CIDs a document
+/// actually uses.
+#[derive(Default)]
+pub struct WidthEncoder {
+    /// CID -> advance width (in 1000-unit glyph space), kept sorted by CID.
+    widths: BTreeMap<u16, f32>,
+}
+
+impl WidthEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the advance width for a used CID.
+    pub fn insert(&mut self, cid: u16, width: f32) {
+        self.widths.insert(cid, width);
+    }
+
+    /// The most common recorded width, suitable for `/DW` (default width),
+    /// or `None` if nothing was recorded.
+    pub fn most_common_width(&self) -> Option<f32> {
+        let mut counts: BTreeMap<u32, u32> = BTreeMap::new();
+        for &w in self.widths.values() {
+            *counts.entry(w.to_bits()).or_insert(0) += 1;
+        }
+        counts.into_iter().max_by_key(|&(_, count)| count).map(|(bits, _)| f32::from_bits(bits))
+    }
+
+    /// Writes the greedily-grouped `/W` entries to `widths_writer`.
+    pub fn write(&self, widths_writer: &mut Widths) {
+        let entries: Vec<(u16, f32)> = self.widths.iter().map(|(&c, &w)| (c, w)).collect();
+        let n = entries.len();
+        let mut i = 0;
+        while i < n {
+            let run_len = equal_run_len(&entries, i);
+            if run_len >= 3 {
+                widths_writer.same(entries[i].0, entries[i + run_len - 1].0, entries[i].1);
+                i += run_len;
+                continue;
+            }
+
+            // Accumulate a consecutive list of contiguous CIDs, stopping
+            // just before a run that's long enough to deserve the range
+            // form instead.
+            let start = i;
+            let mut list = vec![entries[i].1];
+            i += 1;
+            while i < n && entries[i].0 == entries[i - 1].0 + 1 && equal_run_len(&entries, i) < 3 {
+                list.push(entries[i].1);
+                i += 1;
+            }
+            widths_writer.individual(entries[start].0, list.iter().copied());
+        }
+    }
+}
+
+/// Length of the contiguous, equal-width run starting at `entries[i]`.
+fn equal_run_len(entries: &[(u16, f32)], i: usize) -> usize {
+    let mut len = 1;
+    while i + len < entries.len()
+        && entries[i + len].0 == entries[i + len - 1].0 + 1
+        && entries[i + len].1 == entries[i].1
+    {
+        len += 1;
+    }
+    len
+}